@@ -1,19 +1,14 @@
 // src/main.rs
 use std::{
     io::{self, Write},
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-    thread,
     time::{Duration, Instant},
 };
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     execute, // Used for execute! macro
     style::{PrintStyledContent, Stylize, Color}, // Import Color enum for specific colors
-    terminal::{self, Clear, ClearType}, // `self` is needed for `terminal::size()`
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}, // `self` is needed for `terminal::size()`
     QueueableCommand, // Used for stdout.queue()
 };
 use std::io::Result; // Correctly import Result from std::io
@@ -21,61 +16,667 @@ use figlet_rs::FIGfont;
 use time_format::{now, strftime_local};
 use std::str; // Import the str module for from_utf8
 
-// Embed the colossal.flf font file directly into the binary
-// The path is relative to the current source file (src/main.rs)
+// Embed the bundled FIGlet fonts directly into the binary.
+// Paths are relative to the current source file (src/main.rs).
 static COLOSSAL_FONT_BYTES: &[u8] = include_bytes!("../resources/colossal.flf");
+static STANDARD_FONT_BYTES: &[u8] = include_bytes!("../resources/standard.flf");
+static BIG_FONT_BYTES: &[u8] = include_bytes!("../resources/big.flf");
+static SLANT_FONT_BYTES: &[u8] = include_bytes!("../resources/slant.flf");
+
+const BUNDLED_FONTS: &[(&str, &[u8])] = &[
+    ("colossal", COLOSSAL_FONT_BYTES),
+    ("standard", STANDARD_FONT_BYTES),
+    ("big", BIG_FONT_BYTES),
+    ("slant", SLANT_FONT_BYTES),
+];
+
+/// Load the FIGlet font to render with: an arbitrary `.flf` file when
+/// `font_path` is given, otherwise one of the bundled fonts selected by
+/// name (falling back to "colossal" with a warning if the name is unknown).
+fn load_font(font_name: &str, font_path: &Option<String>) -> FIGfont {
+    if let Some(path) = font_path {
+        return FIGfont::from_file(path).unwrap_or_else(|err| {
+            eprintln!("Failed to load font from '{path}': {err}");
+            std::process::exit(1);
+        });
+    }
+
+    let bytes = BUNDLED_FONTS
+        .iter()
+        .find(|(name, _)| *name == font_name)
+        .map(|(_, bytes)| *bytes)
+        .unwrap_or_else(|| {
+            eprintln!("Unknown font '{font_name}', falling back to colossal");
+            COLOSSAL_FONT_BYTES
+        });
+    let content = str::from_utf8(bytes).expect("bundled font is not valid UTF-8");
+    FIGfont::from_content(content).expect("bundled font failed to parse")
+}
+
+/// A single on-screen glyph plus the color it's drawn in.
+#[derive(Clone, Copy, PartialEq)]
+struct Cell {
+    ch: char,
+    color: Color,
+}
+
+impl Cell {
+    const BLANK: Cell = Cell { ch: ' ', color: Color::Reset };
+}
+
+/// A snapshot of every glyph on screen, indexed by `row * width + col`.
+///
+/// Rendering diffs the freshly-built back buffer against the previously
+/// drawn front buffer and only repaints the cells that changed, instead of
+/// clearing and rewriting the whole screen every frame.
+struct ScreenBuffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl ScreenBuffer {
+    /// A buffer of the given size, filled with blank cells.
+    fn blank(width: u16, height: u16) -> Self {
+        ScreenBuffer {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width as usize * height as usize],
+        }
+    }
+
+    fn get(&self, row: u16, col: u16) -> Cell {
+        self.cells[row as usize * self.width as usize + col as usize]
+    }
+
+    fn set(&mut self, row: u16, col: u16, ch: char, color: Color) {
+        self.cells[row as usize * self.width as usize + col as usize] = Cell { ch, color };
+    }
+}
+
+/// Diff `back` against `front`, writing only the cells that changed, then
+/// update `front` to match. Horizontally adjacent changed cells on the same
+/// row *and* sharing the same color are coalesced into a single
+/// `PrintStyledContent` to cut down on cursor moves.
+fn draw_diff(stdout: &mut io::Stdout, front: &mut ScreenBuffer, back: &ScreenBuffer) -> Result<()> {
+    for row in 0..back.height {
+        let mut col = 0;
+        while col < back.width {
+            if back.get(row, col) == front.get(row, col) {
+                col += 1;
+                continue;
+            }
+
+            // Extend the run while cells keep differing from the front
+            // buffer and keep the same color, so a whole changed word in a
+            // single color becomes one write instead of one per character.
+            let run_start = col;
+            let run_color = back.get(row, col).color;
+            let mut run = String::new();
+            while col < back.width
+                && back.get(row, col) != front.get(row, col)
+                && back.get(row, col).color == run_color
+            {
+                run.push(back.get(row, col).ch);
+                front.set(row, col, back.get(row, col).ch, back.get(row, col).color);
+                col += 1;
+            }
+
+            stdout.queue(cursor::MoveTo(run_start, row))?;
+            stdout.queue(PrintStyledContent(run.with(run_color)))?;
+        }
+    }
+    Ok(())
+}
+
+/// How the clock's glyphs are colored: a single fixed color, or a vertical
+/// gradient interpolated row-by-row between two RGB endpoints.
+enum Theme {
+    Solid(Color),
+    Gradient { start: (u8, u8, u8), end: (u8, u8, u8) },
+}
+
+impl Theme {
+    /// The color to draw a given row of the FIGlet art in, where `row` is
+    /// `0..total_rows`.
+    fn color_for_row(&self, row: u16, total_rows: u16, truecolor: bool) -> Color {
+        match self {
+            Theme::Solid(color) => *color,
+            Theme::Gradient { start, end } => {
+                let t = if total_rows <= 1 {
+                    0.0
+                } else {
+                    row as f32 / (total_rows - 1) as f32
+                };
+                let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                let rgb = (lerp(start.0, end.0), lerp(start.1, end.1), lerp(start.2, end.2));
+                resolve_rgb(rgb, truecolor)
+            }
+        }
+    }
+}
+
+/// The terminal advertises truecolor support via `COLORTERM=truecolor` (or
+/// the less common `24bit`). Absent that, assume only the 256-color palette
+/// is safe to use.
+fn supports_truecolor() -> bool {
+    std::env::var("COLORTERM")
+        .map(|v| v == "truecolor" || v == "24bit")
+        .unwrap_or(false)
+}
+
+/// Render an RGB triple as a true 24-bit color, or fall back to the nearest
+/// xterm-256 palette entry when the terminal doesn't advertise truecolor.
+fn resolve_rgb((r, g, b): (u8, u8, u8), truecolor: bool) -> Color {
+    if truecolor {
+        Color::Rgb { r, g, b }
+    } else {
+        Color::AnsiValue(rgb_to_ansi256(r, g, b))
+    }
+}
+
+/// Map an RGB triple to the closest color in the 6x6x6 xterm-256 color
+/// cube (indices 16..=231), which is the standard approximation used by
+/// terminal emulators that lack truecolor support.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // Cube levels are 0, 95, 135, 175, 215, 255; pick the nearest one.
+    let to_cube = |c: u8| if c < 48 { 0 } else if c < 115 { 1 } else { (c - 35) / 40 };
+    let (cr, cg, cb) = (to_cube(r), to_cube(g), to_cube(b));
+    16 + 36 * cr + 6 * cg + cb
+}
+
+/// Resolve a `--color` argument: a named color, a `#rrggbb` hex triple, or
+/// a bare xterm-256 palette index. Falls back to green (with a warning) on
+/// anything unrecognized.
+fn parse_color_arg(s: &str, truecolor: bool) -> Color {
+    if let Some(hex) = s.strip_prefix('#') {
+        let rgb = parse_hex_rgb(hex).unwrap_or_else(|| {
+            eprintln!("Unrecognized --color '{s}', falling back to green");
+            (0, 255, 0)
+        });
+        return resolve_rgb(rgb, truecolor);
+    }
+    if let Ok(index) = s.parse::<u8>() {
+        return Color::AnsiValue(index);
+    }
+    match s.to_lowercase().as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        "darkgrey" | "darkgray" => Color::DarkGrey,
+        other => {
+            eprintln!("Unrecognized --color '{other}', falling back to green");
+            Color::Green
+        }
+    }
+}
+
+/// Parse a `--gradient` endpoint: a `#rrggbb` hex triple or a bare
+/// `r,g,b` tuple.
+fn parse_rgb_arg(s: &str) -> (u8, u8, u8) {
+    if let Some(hex) = s.strip_prefix('#') {
+        return parse_hex_rgb(hex).unwrap_or((0, 255, 0));
+    }
+    let parts: Vec<&str> = s.split(',').collect();
+    if let [r, g, b] = parts[..] {
+        if let (Ok(r), Ok(g), Ok(b)) = (r.parse(), g.parse(), b.parse()) {
+            return (r, g, b);
+        }
+    }
+    eprintln!("Unrecognized gradient color '{s}', falling back to green");
+    (0, 255, 0)
+}
+
+fn parse_hex_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+    // Byte-slicing a `str` below requires ASCII input, since a multi-byte
+    // UTF-8 character could otherwise land the slice boundaries mid-char
+    // and panic instead of falling back to green like every other
+    // malformed-hex case.
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some((r, g, b))
+}
+
+/// Format a duration as `HH:MM:SS`, or `MM:SS` when it's under an hour.
+fn format_duration(d: Duration) -> String {
+    let total_secs = d.as_secs();
+    let hours = total_secs / 3600;
+    let minutes = (total_secs % 3600) / 60;
+    let seconds = total_secs % 60;
+    if hours > 0 {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Parse a duration given as `HH:MM:SS`/`MM:SS`/`SS`, or as suffixed units
+/// like `1h30m`, `90s`. A bare number is treated as seconds.
+fn parse_duration(s: &str) -> Option<Duration> {
+    if s.contains(':') {
+        let parts: Vec<&str> = s.split(':').collect();
+        let nums: Vec<u64> = parts.iter().map(|p| p.parse().ok()).collect::<Option<_>>()?;
+        let secs = match nums.as_slice() {
+            [secs] => *secs,
+            [mins, secs] => mins * 60 + secs,
+            [hours, mins, secs] => hours * 3600 + mins * 60 + secs,
+            _ => return None,
+        };
+        return Some(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: u64 = digits.parse().ok()?;
+        digits.clear();
+        total += match c {
+            'h' => n * 3600,
+            'm' => n * 60,
+            's' => n,
+            _ => return None,
+        };
+    }
+    if !digits.is_empty() {
+        total += digits.parse::<u64>().ok()?;
+    }
+    Some(Duration::from_secs(total))
+}
+
+/// A stopwatch that accumulates elapsed time across start/pause cycles.
+struct Stopwatch {
+    running: bool,
+    accumulated: Duration,
+    started_at: Option<Instant>,
+}
+
+impl Stopwatch {
+    fn new() -> Self {
+        Stopwatch { running: false, accumulated: Duration::ZERO, started_at: None }
+    }
+
+    fn toggle(&mut self) {
+        if self.running {
+            self.accumulated += self.started_at.take().map(|s| s.elapsed()).unwrap_or_default();
+            self.running = false;
+        } else {
+            self.started_at = Some(Instant::now());
+            self.running = true;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.accumulated = Duration::ZERO;
+        self.started_at = self.running.then(Instant::now);
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.accumulated + self.started_at.map(|s| s.elapsed()).unwrap_or_default()
+    }
+}
+
+/// How often a finished countdown's display flips color to draw the eye.
+const FLASH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A countdown timer that runs from a fixed duration down to zero, then
+/// flashes and rings the terminal bell once it's reached.
+struct Countdown {
+    total: Duration,
+    running: bool,
+    remaining_at_pause: Duration,
+    started_at: Option<Instant>,
+    flash_on: bool,
+    last_flash: Instant,
+    bell_rung: bool,
+}
+
+impl Countdown {
+    /// A countdown from `total`, running immediately.
+    fn new(total: Duration) -> Self {
+        Countdown {
+            total,
+            running: true,
+            remaining_at_pause: total,
+            started_at: Some(Instant::now()),
+            flash_on: false,
+            last_flash: Instant::now(),
+            bell_rung: false,
+        }
+    }
+
+    fn toggle(&mut self) {
+        if self.running {
+            self.remaining_at_pause = self.remaining();
+            self.started_at = None;
+            self.running = false;
+        } else {
+            self.started_at = Some(Instant::now());
+            self.running = true;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.remaining_at_pause = self.total;
+        self.started_at = self.running.then(Instant::now);
+        self.flash_on = false;
+        self.bell_rung = false;
+    }
+
+    fn remaining(&self) -> Duration {
+        let elapsed = self.started_at.map(|s| s.elapsed()).unwrap_or_default();
+        self.remaining_at_pause.saturating_sub(elapsed)
+    }
+}
+
+/// The active display source: a wall clock, a stopwatch, or a countdown
+/// timer. Centering and rendering are shared; only the text (and, for a
+/// finished countdown, the flash color) come from the mode.
+enum Mode {
+    Clock { time_format: String, show_date: bool },
+    Stopwatch(Stopwatch),
+    Countdown(Countdown),
+}
+
+impl Mode {
+    /// The current display text, a color override (for countdown flashing),
+    /// and whether the terminal bell should be rung this frame. `timestamp`
+    /// is the single "now" sampled for this frame, shared with anything else
+    /// (like the date line) so they can't disagree about what time it is.
+    fn current_display(&mut self, timestamp: i64) -> (String, Option<Color>, bool) {
+        match self {
+            Mode::Clock { time_format, .. } => (strftime_local(time_format, timestamp).unwrap(), None, false),
+            Mode::Stopwatch(stopwatch) => (format_duration(stopwatch.elapsed()), None, false),
+            Mode::Countdown(countdown) => {
+                let remaining = countdown.remaining();
+                if remaining.is_zero() {
+                    let ring_bell = !countdown.bell_rung;
+                    countdown.bell_rung = true;
+                    if countdown.last_flash.elapsed() >= FLASH_INTERVAL {
+                        countdown.flash_on = !countdown.flash_on;
+                        countdown.last_flash = Instant::now();
+                    }
+                    let color = countdown.flash_on.then_some(Color::Red);
+                    (format_duration(remaining), color, ring_bell)
+                } else {
+                    (format_duration(remaining), None, false)
+                }
+            }
+        }
+    }
+
+    /// Apply a mode-specific keybinding: space to start/pause, `r` to reset.
+    /// The wall clock has no runtime controls.
+    fn handle_key(&mut self, key: &KeyEvent) {
+        let (toggle_key, reset_key) = (KeyCode::Char(' '), KeyCode::Char('r'));
+        match self {
+            Mode::Clock { .. } => {}
+            Mode::Stopwatch(stopwatch) => {
+                if key.code == toggle_key {
+                    stopwatch.toggle();
+                } else if key.code == reset_key {
+                    stopwatch.reset();
+                }
+            }
+            Mode::Countdown(countdown) => {
+                if key.code == toggle_key {
+                    countdown.toggle();
+                } else if key.code == reset_key {
+                    countdown.reset();
+                }
+            }
+        }
+    }
+}
+
+/// Build the `strftime_local` format string for the clock face from the
+/// `--12h` and `--no-seconds` flags.
+fn build_time_format(twelve_hour: bool, show_seconds: bool) -> String {
+    let mut fmt = String::from(if twelve_hour { "%I:%M" } else { "%H:%M" });
+    if show_seconds {
+        fmt.push_str(":%S");
+    }
+    if twelve_hour {
+        fmt.push_str(" %p");
+    }
+    fmt
+}
+
+/// Which `Mode` to build: selected positionally on the command line via
+/// `clock` (the default), `stopwatch`, or `countdown DURATION`.
+enum ModeSelection {
+    Clock,
+    Stopwatch,
+    Countdown(Duration),
+}
+
+/// Parsed CLI configuration: color theme, font selection, display format,
+/// and app mode.
+struct Config {
+    theme: Theme,
+    font_name: String,
+    font_path: Option<String>,
+    time_format: String,
+    show_date: bool,
+    mode: ModeSelection,
+}
+
+/// Parse the CLI flags and positional mode this binary understands:
+/// `--color <name|#rrggbb|index>`, `--gradient <start> <end>`,
+/// `--font-name <name>`, `--font <path>`, `--12h`, `--no-seconds`, `--date`,
+/// and the `clock` / `stopwatch` / `countdown DURATION` mode selector.
+fn parse_config(args: &[String]) -> Config {
+    let truecolor = supports_truecolor();
+    let mut theme = Theme::Solid(Color::Green);
+    let mut font_name = String::from("colossal");
+    let mut font_path = None;
+    let mut twelve_hour = false;
+    let mut show_seconds = true;
+    let mut show_date = false;
+    let mut mode = ModeSelection::Clock;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--color" => {
+                if let Some(value) = args.get(i + 1) {
+                    theme = Theme::Solid(parse_color_arg(value, truecolor));
+                    i += 1;
+                } else {
+                    eprintln!("--color requires a value");
+                }
+            }
+            "--gradient" => {
+                if let (Some(start), Some(end)) = (args.get(i + 1), args.get(i + 2)) {
+                    theme = Theme::Gradient {
+                        start: parse_rgb_arg(start),
+                        end: parse_rgb_arg(end),
+                    };
+                    i += 2;
+                } else {
+                    eprintln!("--gradient requires START and END colors");
+                }
+            }
+            "--font-name" => {
+                if let Some(value) = args.get(i + 1) {
+                    font_name = value.clone();
+                    i += 1;
+                } else {
+                    eprintln!("--font-name requires a value");
+                }
+            }
+            "--font" => {
+                if let Some(value) = args.get(i + 1) {
+                    font_path = Some(value.clone());
+                    i += 1;
+                } else {
+                    eprintln!("--font requires a path");
+                }
+            }
+            "--12h" => twelve_hour = true,
+            "--no-seconds" => show_seconds = false,
+            "--date" => show_date = true,
+            "clock" => mode = ModeSelection::Clock,
+            "stopwatch" => mode = ModeSelection::Stopwatch,
+            "countdown" => {
+                if let Some(value) = args.get(i + 1) {
+                    match parse_duration(value) {
+                        Some(duration) => mode = ModeSelection::Countdown(duration),
+                        None => eprintln!("Unrecognized countdown duration '{value}'"),
+                    }
+                    i += 1;
+                } else {
+                    eprintln!("countdown requires a DURATION");
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    Config {
+        theme,
+        font_name,
+        font_path,
+        time_format: build_time_format(twelve_hour, show_seconds),
+        show_date,
+        mode,
+    }
+}
+
+/// Restores the terminal (cursor, alternate screen, raw mode) when dropped.
+/// Because its destructor runs during unwinding, this keeps the user's
+/// terminal intact even if `run` panics instead of returning an error —
+/// `Drop` runs where a post-call cleanup block would be skipped. [15, 16]
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = execute!(stdout, cursor::Show); // Make the cursor visible again [1, 2]
+        let _ = execute!(stdout, LeaveAlternateScreen); // Hand the user's terminal back untouched
+        let _ = terminal::disable_raw_mode(); // Disable raw mode [17, 18]
+    }
+}
 
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let config = parse_config(&args);
+    let truecolor = supports_truecolor();
+    let font = load_font(&config.font_name, &config.font_path);
+    let mut mode = match config.mode {
+        ModeSelection::Clock => Mode::Clock { time_format: config.time_format, show_date: config.show_date },
+        ModeSelection::Stopwatch => Mode::Stopwatch(Stopwatch::new()),
+        ModeSelection::Countdown(duration) => Mode::Countdown(Countdown::new(duration)),
+    };
+
     let mut stdout = io::stdout();
 
-    // 1. Enable raw mode and hide the cursor for a clean display [1, 2]
+    // 1. Enable raw mode, switch to the alternate screen, and hide the cursor
+    // for a clean display that doesn't disturb the user's existing terminal
+    // contents [1, 2]
     terminal::enable_raw_mode()?;
-    execute!(stdout, cursor::Hide)?;
-
-    // 2. Set up Ctrl+C handling in a separate thread [3]
-    // This allows for graceful exit even when raw mode intercepts signals.
-    let running = Arc::new(AtomicBool::new(true));
-    let r_clone = running.clone();
-    thread::spawn(move |
-
-| -> Result<()> {
-        loop {
-            // Poll for events every 100ms to remain responsive [3]
-            if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key_event) = event::read()? {
+    execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+    let _terminal_guard = TerminalGuard;
+
+    // Run the render loop. `_terminal_guard` restores the terminal on the
+    // way out regardless of whether this returns `Ok`, `Err`, or panics.
+    run(&mut stdout, &config.theme, truecolor, &font, &mut mode)
+}
+
+// How long to block on `event::poll` between checks. Short enough to stay
+// responsive to keypresses and resizes, long enough to avoid spinning.
+const POLL_TIMEOUT: Duration = Duration::from_millis(200);
+
+fn run(
+    stdout: &mut io::Stdout,
+    theme: &Theme,
+    truecolor: bool,
+    font: &FIGfont,
+    mode: &mut Mode,
+) -> Result<()> {
+    // Track what's currently on screen so we only redraw when the display
+    // text or its color override actually changes, or the terminal is
+    // resized, instead of repainting unconditionally once a second.
+    let mut last_display_string = String::new();
+    let mut last_color_override: Option<Color> = None;
+    let mut last_terminal_size = terminal::size()?;
+
+    // The front buffer mirrors what's actually drawn on the terminal; the
+    // back buffer is rebuilt from scratch each redraw and diffed against it.
+    let mut front = ScreenBuffer::blank(last_terminal_size.0, last_terminal_size.1);
+
+    // Main application loop: event-driven, continues until Ctrl+C is detected
+    // or an error occurs. Polling (rather than a fixed `thread::sleep`) keeps
+    // the loop responsive to keypresses and resize events in between ticks.
+    loop {
+        let mut resized = false;
+
+        if event::poll(POLL_TIMEOUT)? {
+            match event::read()? {
+                Event::Key(key_event) => {
                     // Check for Ctrl+C (KeyCode::Char('c') with KeyModifiers::CONTROL)
                     if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
-                        r_clone.store(false, Ordering::SeqCst); // Signal the main thread to stop
                         break;
                     }
+                    mode.handle_key(&key_event);
                 }
-            }
-            // Also check if the main thread has already signaled to stop (e.g., on error)
-            if!r_clone.load(Ordering::SeqCst) {
-                break;
+                Event::Resize(w, h) => {
+                    last_terminal_size = (w, h);
+                    // Reallocate both buffers to the new size; a blank front
+                    // buffer guarantees the next diff is a full repaint.
+                    front = ScreenBuffer::blank(w, h);
+                    stdout.queue(Clear(ClearType::All))?;
+                    resized = true;
+                }
+                _ => {}
             }
         }
-        Ok(())
-    });
 
-    // 3. Load the custom "Colossal" FIGlet font from the embedded bytes
-    // Convert the byte slice to a string slice, assuming valid UTF-8 [4]
-    let font_content = str::from_utf8(COLOSSAL_FONT_BYTES).expect("Colossal font file is not valid UTF-8");
-    let standard_font = FIGfont::from_content(font_content).unwrap(); // Use from_content [5]
+        // Sample "now" once per frame so the clock face and the date line
+        // below it (if shown) can never disagree about what time it is.
+        let current_timestamp = now().unwrap();
 
-    // Initialize time tracking for consistent updates
-    let mut last_update_time = Instant::now();
-    let update_interval = Duration::from_secs(1); // Update every 1 second
+        // Ask the active mode (clock, stopwatch, or countdown) for the text
+        // to display, any color override, and whether to ring the bell [6]
+        let (display_string, color_override, ring_bell) = mode.current_display(current_timestamp);
 
-    // Main application loop: continues until Ctrl+C is detected or an error occurs
-    while running.load(Ordering::SeqCst) {
-        // 4. Get the current time and format it as HH:MM:SS [6]
-        let current_timestamp = now().unwrap();
-        let time_string = strftime_local("%H:%M:%S", current_timestamp).unwrap();
+        if ring_bell {
+            stdout.write_all(b"\x07")?;
+        }
+
+        // Skip the redraw entirely if nothing the user can see has changed.
+        if !resized && display_string == last_display_string && color_override == last_color_override {
+            continue;
+        }
 
-        // 5. Generate the large ASCII art representation of the time [7, 8]
-        let figure = standard_font.convert(&time_string);
-        let ascii_art_figure = figure.expect("Could not convert time to ASCII art");
+        // Generate the large ASCII art representation of the display text [7, 8].
+        // Not every font covers every character (arbitrary `--font` files and
+        // the AM/PM suffix raise the odds of a miss), so return an error
+        // instead of panicking mid-render; `TerminalGuard` restores the
+        // terminal either way. The date line is not drawn with the font.
+        let ascii_art_figure = match font.convert(&display_string) {
+            Some(figure) => figure,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Font cannot render '{display_string}': missing glyph"),
+                ));
+            }
+        };
         let ascii_art_string = ascii_art_figure.to_string(); // Convert FIGure to String [9]
 
         // Calculate the dimensions of the generated ASCII art
@@ -83,42 +684,157 @@ fn main() -> Result<()> {
         let ascii_art_height = ascii_art_lines.len() as u16;
         let ascii_art_width = ascii_art_lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
 
-        // 6. Get the current terminal dimensions [10, 11, 12]
-        let (terminal_width, terminal_height) = terminal::size()?;
+        // Use the terminal dimensions from the last known resize (or the
+        // initial size) rather than re-querying every tick [10, 11, 12]
+        let (terminal_width, terminal_height) = last_terminal_size;
 
-        // 7. Calculate the top-left coordinates to center the ASCII art
+        // Calculate the top-left coordinates to center the ASCII art
         let start_col = terminal_width.saturating_sub(ascii_art_width) / 2;
         let start_row = terminal_height.saturating_sub(ascii_art_height) / 2;
 
-        // 8. Queue terminal commands for efficient, flicker-free updates
-        // Clear the entire screen
-        stdout.queue(Clear(ClearType::All))?;
+        // Render the centered art into a back buffer (space-filled outside
+        // the art), then diff it against the front buffer so only the cells
+        // that actually changed get written.
+        let mut back = ScreenBuffer::blank(terminal_width, terminal_height);
+        for (i, line) in ascii_art_lines.iter().enumerate() {
+            let row = start_row + i as u16;
+            if row >= terminal_height {
+                break;
+            }
+            let color = color_override.unwrap_or_else(|| theme.color_for_row(i as u16, ascii_art_height, truecolor));
+            for (j, ch) in line.chars().enumerate() {
+                let col = start_col + j as u16;
+                if col >= terminal_width {
+                    break;
+                }
+                back.set(row, col, ch, color);
+            }
+        }
 
-        // Print each line of the ASCII art, moving the cursor for each line
-        let mut current_print_row = start_row;
-        for line in ascii_art_lines {
-            stdout.queue(cursor::MoveTo(start_col, current_print_row))?;
-            // Print in green color
-            stdout.queue(PrintStyledContent(line.to_string().with(Color::Green)))?;
-            current_print_row += 1;
+        if let Mode::Clock { show_date: true, .. } = mode {
+            let date_string = strftime_local("%Y-%m-%d", current_timestamp).unwrap();
+            let date_row = start_row + ascii_art_height;
+            if date_row < terminal_height {
+                let date_col = terminal_width.saturating_sub(date_string.len() as u16) / 2;
+                for (j, ch) in date_string.chars().enumerate() {
+                    let col = date_col + j as u16;
+                    if col >= terminal_width {
+                        break;
+                    }
+                    back.set(date_row, col, ch, Color::Grey);
+                }
+            }
         }
 
-        // 9. Flush all queued commands to the terminal at once
+        draw_diff(stdout, &mut front, &back)?;
+
+        // Flush all queued commands to the terminal at once
         stdout.flush()?;
 
-        // 10. Control the update rate to approximately 1 second [13, 14]
-        let elapsed = last_update_time.elapsed();
-        if elapsed < update_interval {
-            thread::sleep(update_interval - elapsed); // Sleep for the remaining time
-        }
-        last_update_time = Instant::now(); // Reset the timer for the next update
+        last_display_string = display_string;
+        last_color_override = color_override;
     }
 
-    // 11. Cleanup: Restore terminal state before exiting [15, 16]
-    // This is crucial to prevent a "corrupted" terminal after the application closes.
-    execute!(stdout, cursor::Show)?; // Make the cursor visible again [1, 2]
-    terminal::disable_raw_mode()?; // Disable raw mode [17, 18]
-    execute!(stdout, Clear(ClearType::All))?; // Clear the screen one last time [10, 11, 12]
-
     Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_rgb_parses_valid_triples() {
+        assert_eq!(parse_hex_rgb("ff8800"), Some((255, 136, 0)));
+        assert_eq!(parse_hex_rgb("000000"), Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn parse_hex_rgb_rejects_wrong_length() {
+        assert_eq!(parse_hex_rgb("12345"), None);
+        assert_eq!(parse_hex_rgb("1234567"), None);
+    }
+
+    #[test]
+    fn parse_hex_rgb_rejects_invalid_digits() {
+        assert_eq!(parse_hex_rgb("zz0000"), None);
+    }
+
+    #[test]
+    fn parse_hex_rgb_rejects_non_ascii_instead_of_panicking() {
+        // "é" is 2 bytes, so this is 6 bytes but only 5 chars; slicing by
+        // byte index would otherwise land mid-character and panic.
+        assert_eq!(parse_hex_rgb("1é234"), None);
+    }
+
+    #[test]
+    fn parse_color_arg_resolves_named_colors() {
+        assert_eq!(parse_color_arg("red", false), Color::Red);
+        assert_eq!(parse_color_arg("Green", false), Color::Green);
+    }
+
+    #[test]
+    fn parse_color_arg_resolves_ansi_index() {
+        assert_eq!(parse_color_arg("196", false), Color::AnsiValue(196));
+    }
+
+    #[test]
+    fn parse_color_arg_resolves_hex_with_truecolor() {
+        assert_eq!(parse_color_arg("#00ff00", true), Color::Rgb { r: 0, g: 255, b: 0 });
+    }
+
+    #[test]
+    fn parse_color_arg_falls_back_to_green_on_unknown_name() {
+        assert_eq!(parse_color_arg("bogus", false), Color::Green);
+    }
+
+    #[test]
+    fn rgb_to_ansi256_maps_cube_corners() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+    }
+
+    #[test]
+    fn build_time_format_24h_no_seconds() {
+        assert_eq!(build_time_format(false, false), "%H:%M");
+    }
+
+    #[test]
+    fn build_time_format_24h_with_seconds() {
+        assert_eq!(build_time_format(false, true), "%H:%M:%S");
+    }
+
+    #[test]
+    fn build_time_format_12h_no_seconds() {
+        assert_eq!(build_time_format(true, false), "%I:%M %p");
+    }
+
+    #[test]
+    fn build_time_format_12h_with_seconds() {
+        assert_eq!(build_time_format(true, true), "%I:%M:%S %p");
+    }
+
+    #[test]
+    fn parse_duration_plain_seconds() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn parse_duration_colon_formats() {
+        assert_eq!(parse_duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1:30"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1:02:03"), Some(Duration::from_secs(3723)));
+    }
+
+    #[test]
+    fn parse_duration_suffix_formats() {
+        assert_eq!(parse_duration("1h30m10s"), Some(Duration::from_secs(3600 + 1800 + 10)));
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_input() {
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("1:2:3:4"), None);
+    }
 }
\ No newline at end of file